@@ -23,7 +23,6 @@ use std::env;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::OnceLock;
 
 use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
 use minify_html::minify;
@@ -34,81 +33,180 @@ use oxc_minifier::{CompressOptions, Minifier, MinifierOptions};
 use oxc_parser::Parser;
 use oxc_span::SourceType;
 
-static NO_MANGLE: OnceLock<Vec<&str>> = OnceLock::new();
-
-fn set_nomangle(files: Vec<&'static str>) {
-    if !files.is_empty() && NO_MANGLE.get().is_none() {
-        NO_MANGLE
-            .set(files)
-            .expect("Failed to set NO_MANGLE file list: already initialiazed")
+/// Decides whether web files should actually be minified and mangled.
+///
+/// Minifying every asset on every build slows down incremental compiles and
+/// makes shipped JS impossible to debug, so by default only release builds
+/// minify: the `PROFILE` env var Cargo exports for build scripts is inspected
+/// and minification is enabled only when it is `"release"`. This can be forced
+/// either way with the `TCLOUD_ASSETS_MINIFY` env var (`1`/`true`/`yes`/`on` to enable,
+/// anything else to disable), mirroring websurfx's `PKG_ENV=prod` gate.
+fn minify_enabled() -> bool {
+    if let Some(value) = env::var_os("TCLOUD_ASSETS_MINIFY") {
+        return matches!(value.to_string_lossy().trim(), "1" | "true" | "yes" | "on");
     }
+    env::var("PROFILE").map(|p| p == "release").unwrap_or(false)
 }
 
-fn check_nomangle(file: &str) -> bool {
-    if let Some(nomangle) = NO_MANGLE.get() {
-        nomangle.contains(&file)
+/// Guesses the content type of an asset from its extension, so a server can set
+/// the right `Content-Type` header when serving the embedded bytes.
+fn content_type(file: &str) -> &'static str {
+    if file.ends_with(".css") {
+        "text/css"
+    } else if file.ends_with(".js") {
+        "text/javascript"
+    } else if file.ends_with(".html") {
+        "text/html"
+    } else if file.ends_with(".json") {
+        "application/json"
+    } else if file.ends_with(".svg") {
+        "image/svg+xml"
+    } else if file.ends_with(".png") {
+        "image/png"
+    } else if file.ends_with(".jpg") || file.ends_with(".jpeg") {
+        "image/jpeg"
+    } else if file.ends_with(".wasm") {
+        "application/wasm"
     } else {
-        false
+        "application/octet-stream"
     }
 }
 
-static OTHER_EXTENSIONS: OnceLock<Vec<&str>> = OnceLock::new();
+/// A single asset emitted into OUT_DIR, used to build the generated registry.
+///
+/// `logical` is the stable path the asset is addressed by (e.g.
+/// `assets/example.css`). `disk` is where the bytes were actually written under
+/// OUT_DIR; it equals `logical` normally, or carries a content hash
+/// (`assets/example.9f3ab1c2.css`) when cache-busting is enabled.
+struct Asset {
+    logical: String,
+    disk: String,
+    content_type: &'static str,
+}
 
-fn set_other_extensions(ext: Vec<&'static str>) {
-    if !ext.is_empty() && OTHER_EXTENSIONS.get().is_none() {
-        OTHER_EXTENSIONS
-            .set(ext)
-            .expect("Failed to set OTHER_EXTENSIONS list: already initialiazed")
-    }
+/// Whether output filenames should be content-hashed for cache-busting.
+///
+/// When enabled (via `TCLOUD_ASSETS_HASH=1`/`true`/`on`), each emitted file is
+/// written as `name.<hash>.ext` and the generated registry gains a manifest that
+/// resolves the logical path to the hashed one, so the app can serve immutable,
+/// far-future `Cache-Control` headers.
+fn hash_enabled() -> bool {
+    env::var_os("TCLOUD_ASSETS_HASH")
+        .map(|value| matches!(value.to_string_lossy().trim(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
 }
 
-fn check_extension(file: &str) -> bool {
-    if let Some(other_extensions) = OTHER_EXTENSIONS.get() {
-        for extension in other_extensions {
-            if file.ends_with(extension) {
-                return true;
-            }
-        }
+/// Builds the on-disk file name for `name`, inserting the first 8 hex characters
+/// of the SHA-256 of `bytes` before the last extension (so nested assets hash
+/// independently and the extension is preserved for content-type detection).
+fn hashed_name(name: &str, bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(bytes);
+    let hash: String = digest.iter().take(4).map(|b| format!("{b:02x}")).collect();
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{stem}.{hash}.{ext}"),
+        None => format!("{name}.{hash}"),
     }
-    false
 }
 
-fn get_filename(path: &Path) -> &str {
-    path.iter().last().unwrap().to_str().unwrap()
+/// A minified web file, plus the JSON source map generated alongside it when
+/// source maps are enabled.
+struct Minified {
+    code: String,
+    map: Option<String>,
+}
+
+impl Minified {
+    /// A minified file with no source map (used by HTML and JSON).
+    fn plain(code: String) -> Self {
+        Self { code, map: None }
+    }
 }
 
-fn minify_js(path: &PathBuf, src: &str) -> String {
+fn minify_js(path: &PathBuf, src: &str, mangle: bool, source_map: bool) -> Minified {
     let allocator = Allocator::default();
     let ret = Parser::new(&allocator, src, SourceType::cjs()).parse();
     let mut program = ret.program;
     let options = MinifierOptions {
-        mangle: !check_nomangle(get_filename(path)),
+        mangle,
         compress: CompressOptions::default(),
     };
     let ret = Minifier::new(options).build(&allocator, &mut program);
-    CodeGenerator::new()
+    let ret = CodeGenerator::new()
         .with_options(CodegenOptions {
             minify: true,
+            // Point the map back at the original asset path, relative to the crate root.
+            source_map_path: source_map.then(|| path.clone()),
             ..CodegenOptions::default()
         })
         .with_mangler(ret.mangler)
-        .build(&program)
-        .code
+        .build(&program);
+    Minified {
+        code: ret.code,
+        map: ret.map.map(|map| map.to_json_string()),
+    }
 }
 
-fn minify_css(path: &str, src: &str) -> String {
-    let mut stylesheet = StyleSheet::parse(src, ParserOptions::default())
-        .unwrap_or_else(|e| panic!("Invalid CSS file '{path}', cannot parse it: {e}"));
+fn minify_css(path: &str, src: &str, source_map: bool) -> Minified {
+    let mut stylesheet = StyleSheet::parse(
+        src,
+        ParserOptions {
+            filename: path.to_string(),
+            ..ParserOptions::default()
+        },
+    )
+    .unwrap_or_else(|e| panic!("Invalid CSS file '{path}', cannot parse it: {e}"));
     stylesheet
         .minify(MinifyOptions::default())
         .unwrap_or_else(|e| panic!("Cannot minify CSS file '{path}': {e}"));
-    stylesheet
+    let mut map = source_map.then(|| parcel_sourcemap::SourceMap::new("/"));
+    let res = stylesheet
         .to_css(PrinterOptions {
             minify: true,
+            source_map: map.as_mut(),
             ..PrinterOptions::default()
         })
-        .unwrap_or_else(|e| panic!("Cannot get minified CSS of file '{path}': {e}"))
-        .code
+        .unwrap_or_else(|e| panic!("Cannot get minified CSS of file '{path}': {e}"));
+    let map = map.map(|mut map| {
+        // Embed the original source so browser stack traces show the real file.
+        let _ = map.set_source_content(0, src);
+        map.to_json(None)
+            .unwrap_or_else(|e| panic!("Cannot serialize source map for '{path}': {e}"))
+    });
+    Minified {
+        code: res.code,
+        map,
+    }
+}
+
+fn minify_json(path: &str, src: &str) -> String {
+    // Reject malformed input like the other minifiers do, but discard the parsed
+    // value: round-tripping through `serde_json::Value` would re-sort object keys
+    // and re-encode numbers that are not 64-bit integers through `f64`. Instead strip only insignificant
+    // whitespace from the token stream, preserving key order and number literals.
+    serde_json::from_str::<serde_json::Value>(src)
+        .unwrap_or_else(|e| panic!("Invalid JSON file '{path}', cannot parse it: {e}"));
+    let mut out = String::with_capacity(src.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in src.chars() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+            out.push(c);
+        } else if !c.is_ascii_whitespace() {
+            out.push(c);
+        }
+    }
+    out
 }
 
 fn minify_html(path: &str, src: &str) -> String {
@@ -116,54 +214,129 @@ fn minify_html(path: &str, src: &str) -> String {
         .unwrap_or_else(|e| panic!("Failed to minify HTML file '{path}': {e}"))
 }
 
-fn handle_file(file: PathBuf, out_dir: &Path) {
+/// Whether source maps should be generated for minified JS and CSS.
+///
+/// Because minification mangles identifiers and strips formatting, production
+/// errors are untraceable; enabling this (via `TCLOUD_ASSETS_SOURCEMAP=1`) writes
+/// a sibling `<name>.ext.map` and appends a `sourceMappingURL` comment so stack
+/// traces point back at the original source.
+fn source_map_enabled() -> bool {
+    env::var_os("TCLOUD_ASSETS_SOURCEMAP")
+        .map(|value| matches!(value.to_string_lossy().trim(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// The on-disk file name for `name`, content-hashed over `bytes` when
+/// cache-busting is enabled, otherwise `name` unchanged.
+fn disk_name(cfg: &Assets, name: &str, bytes: &[u8]) -> String {
+    if cfg.hash {
+        hashed_name(name, bytes)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Writes `bytes` for asset `name` to `out_dir/disk_name` and records the result
+/// in `registry`, keyed by the stable logical path.
+fn emit(
+    out_dir: &Path,
+    rel: &Path,
+    name: &str,
+    disk_name: &str,
+    bytes: &[u8],
+    registry: &mut Vec<Asset>,
+) {
+    let new_file_path = out_dir.join(disk_name);
+    fs::write(&new_file_path, bytes)
+        .unwrap_or_else(|e| panic!("Failed to write file {}: {e}", new_file_path.display()));
+    let norm = |name: &str| {
+        rel.join(name)
+            .to_str()
+            .expect("Invalid path UTF-8")
+            .replace('\\', "/")
+    };
+    let logical = norm(name);
+    registry.push(Asset {
+        content_type: content_type(&logical),
+        disk: norm(disk_name),
+        logical,
+    });
+}
+
+fn handle_file(cfg: &Assets, file: PathBuf, out_dir: &Path, rel: &Path, registry: &mut Vec<Asset>) {
     let path = file.to_str().expect("Invalid path UTF-8");
+    let name = file.file_name().unwrap().to_str().unwrap().to_owned();
+    let read = || fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
 
     // If it has an accepted extension it is copied without modification
-    if check_extension(get_filename(&file)) {
-        let mut new_file_path: PathBuf = out_dir.into();
-        new_file_path.push(file.file_name().unwrap());
-        fs::copy(&file, &new_file_path).unwrap_or_else(|_| {
-            panic!(
-                "Failed to copy file from {path} to {}",
-                new_file_path.display()
-            )
-        });
+    if cfg.check_extension(&name) {
+        let bytes = fs::read(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+        emit(out_dir, rel, &name, &disk_name(cfg, &name, &bytes), &bytes, registry);
+        return;
+    }
+
+    // Only recognized web files are handled; anything else is ignored.
+    let is_web = path.ends_with(".css")
+        || path.ends_with(".js")
+        || path.ends_with(".html")
+        || path.ends_with(".json");
+    if !is_web {
+        return;
+    }
+
+    // In non-production builds the web files are copied verbatim: no minify, no
+    // JS mangling, so incremental builds stay fast and the output stays debuggable.
+    if !cfg.minify {
+        let bytes = fs::read(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+        emit(out_dir, rel, &name, &disk_name(cfg, &name, &bytes), &bytes, registry);
         return;
     }
 
     // If it is a Web File it is minified and then written
-    // If it's none of them the file is ignored
-    let minified = if path.ends_with(".css") {
-        minify_css(
-            path,
-            &fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}")),
-        )
+    let source_map = cfg.source_map;
+    let mut minified = if path.ends_with(".css") {
+        minify_css(path, &read(), source_map)
     } else if path.ends_with(".js") {
-        minify_js(
-            &file,
-            &fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}")),
-        )
+        minify_js(&file, &read(), !cfg.check_nomangle(&name), source_map)
     } else if path.ends_with(".html") {
-        minify_html(
-            path,
-            &fs::read_to_string(&file).unwrap_or_else(|e| panic!("Failed to read {path}: {e}")),
-        )
+        Minified::plain(minify_html(path, &read()))
     } else {
-        return;
+        Minified::plain(minify_json(path, &read()))
     };
-    let mut new_file_path: PathBuf = out_dir.into();
-    new_file_path.push(file.file_name().unwrap());
-    fs::write(&new_file_path, minified).unwrap_or_else(|_| {
-        panic!(
-            "Failed to write minified file {}",
-            new_file_path.to_str().unwrap()
-        )
-    });
+
+    // Name the asset from the minified bytes (before the map comment is appended),
+    // so the map can share that content hash and be cached just as aggressively.
+    let disk = disk_name(cfg, &name, minified.code.as_bytes());
+
+    // When a source map was produced, write it next to the asset under the same
+    // (hashed) base name and point the minified output at it. CSS cannot use `//`
+    // line comments, so the directive syntax must match the asset type.
+    if let Some(map) = minified.map {
+        let map_name = format!("{disk}.map");
+        fs::write(out_dir.join(&map_name), map)
+            .unwrap_or_else(|e| panic!("Failed to write source map {map_name}: {e}"));
+        if path.ends_with(".css") {
+            minified
+                .code
+                .push_str(&format!("\n/*# sourceMappingURL={map_name} */"));
+        } else {
+            minified
+                .code
+                .push_str(&format!("\n//# sourceMappingURL={map_name}"));
+        }
+    }
+    emit(out_dir, rel, &name, &disk, minified.code.as_bytes(), registry);
 }
 
-fn handle_directory(directory: PathBuf, out_dir: &PathBuf) {
-    let mut new_dir = out_dir.clone();
+fn handle_directory(
+    cfg: &Assets,
+    directory: PathBuf,
+    out_dir: &Path,
+    rel: &Path,
+    registry: &mut Vec<Asset>,
+) {
+    let new_rel = rel.join(directory.file_name().unwrap());
+    let mut new_dir: PathBuf = out_dir.into();
     new_dir.push(directory.file_name().unwrap());
     fs::create_dir_all(&new_dir)
         .unwrap_or_else(|_| panic!("Failed to create {}", directory.display()));
@@ -173,35 +346,229 @@ fn handle_directory(directory: PathBuf, out_dir: &PathBuf) {
     {
         if let Ok(file_type) = direntry.file_type() {
             if file_type.is_dir() {
-                handle_directory(direntry.path(), &new_dir);
+                handle_directory(cfg, direntry.path(), &new_dir, &new_rel, registry);
             } else if file_type.is_file() {
-                handle_file(direntry.path(), &new_dir);
+                handle_file(cfg, direntry.path(), &new_dir, &new_rel, registry);
             }
         }
     }
 }
 
+/// Writes the generated asset registry to `OUT_DIR/tcloud_assets.rs`.
+///
+/// The user pulls it in with:
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/tcloud_assets.rs"));
+/// ```
+/// which defines a sorted `ASSETS` table and a `get` helper mapping a logical
+/// relative path (e.g. `"assets/example.css"`) to the embedded bytes of its
+/// emitted (minified or verbatim) copy, alongside a content-type hint for HTTP responses.
+fn write_registry(out_dir: &Path, mut registry: Vec<Asset>) {
+    registry.sort_by(|a, b| a.logical.cmp(&b.logical));
+    let mut src = String::from(
+        "// @generated by tcloud-assets-include. Do not edit.\n\
+         /// Embedded assets, sorted by logical path for binary search.\n\
+         /// Each entry is `(logical path, emitted bytes, content type)`.\n\
+         pub static ASSETS: &[(&str, &[u8], &str)] = &[\n",
+    );
+    for asset in &registry {
+        // Bytes are read from the on-disk (possibly hashed) file, but keyed by
+        // the stable logical path.
+        src.push_str(&format!(
+            "    ({:?}, include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{}\")), {:?}),\n",
+            asset.logical, asset.disk, asset.content_type
+        ));
+    }
+    src.push_str(
+        "];\n\n\
+         /// Maps each logical path to its emitted (content-hashed) path, for\n\
+         /// cache-busting URLs. Identical to the logical path when hashing is off.\n\
+         pub static MANIFEST: &[(&str, &str)] = &[\n",
+    );
+    for asset in &registry {
+        src.push_str(&format!("    ({:?}, {:?}),\n", asset.logical, asset.disk));
+    }
+    src.push_str(
+        "];\n\n\
+         /// Returns the embedded bytes of the asset at `path`, if any.\n\
+         pub fn get(path: &str) -> Option<&'static [u8]> {\n\
+         \x20   ASSETS\n\
+         \x20       .binary_search_by(|(p, _, _)| p.cmp(&path))\n\
+         \x20       .ok()\n\
+         \x20       .map(|i| ASSETS[i].1)\n\
+         }\n\n\
+         /// Returns the content-type hint of the asset at `path`, if any.\n\
+         pub fn content_type(path: &str) -> Option<&'static str> {\n\
+         \x20   ASSETS\n\
+         \x20       .binary_search_by(|(p, _, _)| p.cmp(&path))\n\
+         \x20       .ok()\n\
+         \x20       .map(|i| ASSETS[i].2)\n\
+         }\n\n\
+         /// Resolves a logical path (e.g. `\"assets/example.css\"`) to its emitted,\n\
+         /// content-hashed path for use in cache-busted URLs.\n\
+         pub fn resolve(path: &str) -> Option<&'static str> {\n\
+         \x20   MANIFEST\n\
+         \x20       .binary_search_by(|(p, _)| p.cmp(&path))\n\
+         \x20       .ok()\n\
+         \x20       .map(|i| MANIFEST[i].1)\n\
+         }\n",
+    );
+    let path = out_dir.join("tcloud_assets.rs");
+    fs::write(&path, src)
+        .unwrap_or_else(|e| panic!("Failed to write asset registry {}: {e}", path.display()));
+}
+
 /// Copies assets (web files and/or binaries) into OUT_DIR.
 /// They can be then included into the executable with [`include_str`] or [`include_bytes`].
 ///
-/// By default, files are ignored unless they end with `.html`, `.js`, or `.css`. If you want to
-/// add some other binary files you can specify their extension or ending in `other_extensions`.
+/// By default, files are ignored unless they end with `.html`, `.js`, `.css`, or `.json`. If you
+/// want to add some other binary files you can specify their extension or ending in `other_extensions`.
 ///
-/// HTML, JS, and CSS files will be minified to avoid using too much space. JavaScript
+/// HTML, JS, CSS, and JSON files will be minified to avoid using too much space, but only in
+/// release builds: in debug builds they are copied verbatim (no minify, no mangling) to keep
+/// incremental compiles fast and the output debuggable. Set `TCLOUD_ASSETS_MINIFY=1`/`0` to
+/// override this regardless of the build profile. JavaScript
 /// files are also mangled, which means that variables are shrinked to occupy less space.
 /// If this behavior breaks some of your scripts, you can disable it for a specific script by
 /// specifying its filename in the `no_mangle` argument.
 ///
+/// Alongside the copied/minified files, a registry is generated at
+/// `OUT_DIR/tcloud_assets.rs` which can be included with
+/// `include!(concat!(env!("OUT_DIR"), "/tcloud_assets.rs"))` to obtain a sorted
+/// `ASSETS` table and a `get(path)` helper that maps a logical relative path to
+/// the embedded bytes of its emitted (minified or verbatim) copy.
+///
+/// Set `TCLOUD_ASSETS_HASH=1` to content-hash output filenames
+/// (`example.9f3ab1c2.css`) for cache-busting; the generated `MANIFEST`/`resolve`
+/// helpers then map a logical path to its hashed path for immutable caching.
+///
+/// Set `TCLOUD_ASSETS_SOURCEMAP=1` to emit a sibling `<name>.ext.map` for each
+/// minified JS and CSS file and append a `sourceMappingURL` comment, so browser
+/// stack traces point back at the original source.
+///
 /// - `path`: Path to the assets (relative to the root of the project).
 /// - `other_extensions`: Files to include other than .html, .js or .css files (will be just copied).
 /// - `no_mangle`: Specify which JS files should not be mangled.
+///
+/// This is a thin wrapper over [`AssetsBuilder`]; use the builder directly when a
+/// single build script needs to include several asset directories with different
+/// settings.
 pub fn include(path: &str, other_extensions: Vec<&'static str>, no_mangle: Vec<&'static str>) {
-    set_other_extensions(other_extensions);
-    set_nomangle(no_mangle);
-    let out_dir =
-        PathBuf::from(env::var_os("OUT_DIR").expect("Failed to get OUT_DIR env variable"));
-    handle_directory(PathBuf::from(path), &out_dir);
-    println!("cargo:rerun-if-changed={path}");
+    AssetsBuilder::new()
+        .other_extensions(other_extensions)
+        .no_mangle(no_mangle)
+        .include(path);
+}
+
+/// Builder for an asset-inclusion run.
+///
+/// Each builder owns its own `other_extensions`, `no_mangle` list and minify/hash/
+/// source-map toggles, so a build script can call [`AssetsBuilder::include`] more
+/// than once with independent configuration — unlike the old process-wide
+/// `OnceLock`s, which silently ignored everything after the first call.
+/// Every call rewrites `OUT_DIR/tcloud_assets.rs`, so the generated registry
+/// lists only the assets of the most recent call.
+///
+/// The minify, hash and source-map toggles default to the environment-based
+/// behavior (`TCLOUD_ASSETS_MINIFY`/`PROFILE`, `TCLOUD_ASSETS_HASH`,
+/// `TCLOUD_ASSETS_SOURCEMAP`) unless set explicitly.
+#[derive(Default)]
+pub struct AssetsBuilder {
+    other_extensions: Vec<&'static str>,
+    no_mangle: Vec<&'static str>,
+    minify: Option<bool>,
+    hash: Option<bool>,
+    source_map: Option<bool>,
+}
+
+impl AssetsBuilder {
+    /// Creates a builder with default (environment-based) settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files to include other than .html, .js, .css or .json files (just copied).
+    pub fn other_extensions(mut self, other_extensions: Vec<&'static str>) -> Self {
+        self.other_extensions = other_extensions;
+        self
+    }
+
+    /// JS files that should not be mangled.
+    pub fn no_mangle(mut self, no_mangle: Vec<&'static str>) -> Self {
+        self.no_mangle = no_mangle;
+        self
+    }
+
+    /// Forces minification on or off, overriding the build-profile default.
+    pub fn minify(mut self, minify: bool) -> Self {
+        self.minify = Some(minify);
+        self
+    }
+
+    /// Forces content-hashed filenames on or off.
+    pub fn hash(mut self, hash: bool) -> Self {
+        self.hash = Some(hash);
+        self
+    }
+
+    /// Forces source-map generation on or off.
+    pub fn source_map(mut self, source_map: bool) -> Self {
+        self.source_map = Some(source_map);
+        self
+    }
+
+    /// Resolves the configuration, filling unset toggles from the environment.
+    pub fn build(self) -> Assets {
+        Assets {
+            other_extensions: self.other_extensions,
+            no_mangle: self.no_mangle,
+            minify: self.minify.unwrap_or_else(minify_enabled),
+            hash: self.hash.unwrap_or_else(hash_enabled),
+            source_map: self.source_map.unwrap_or_else(source_map_enabled),
+        }
+    }
+
+    /// Convenience for `self.build().include(path)`.
+    pub fn include(self, path: &str) {
+        self.build().include(path);
+    }
+}
+
+/// A resolved asset configuration, produced by [`AssetsBuilder::build`].
+pub struct Assets {
+    other_extensions: Vec<&'static str>,
+    no_mangle: Vec<&'static str>,
+    minify: bool,
+    hash: bool,
+    source_map: bool,
+}
+
+impl Assets {
+    fn check_extension(&self, file: &str) -> bool {
+        self.other_extensions.iter().any(|ext| file.ends_with(ext))
+    }
+
+    fn check_nomangle(&self, file: &str) -> bool {
+        self.no_mangle.contains(&file)
+    }
+
+    /// Copies assets (web files and/or binaries) into OUT_DIR and generates the
+    /// `tcloud_assets.rs` registry, using this configuration. See [`include`] for
+    /// the full description of the output.
+    pub fn include(&self, path: &str) {
+        let out_dir =
+            PathBuf::from(env::var_os("OUT_DIR").expect("Failed to get OUT_DIR env variable"));
+        let mut registry = Vec::new();
+        handle_directory(
+            self,
+            PathBuf::from(path),
+            &out_dir,
+            Path::new(""),
+            &mut registry,
+        );
+        write_registry(&out_dir, registry);
+        println!("cargo:rerun-if-changed={path}");
+    }
 }
 
 #[cfg(test)]
@@ -210,12 +577,23 @@ mod tests {
     use std::path::PathBuf;
     use tempfile::tempdir;
 
+    fn test_assets() -> crate::Assets {
+        // Force the minify path regardless of the test runner's build profile.
+        crate::AssetsBuilder::new()
+            .other_extensions(vec![".test"])
+            .no_mangle(vec!["example.nomangle.js"])
+            .minify(true)
+            .build()
+    }
+
     fn test_include(tmpdir: PathBuf) {
-        crate::set_other_extensions(vec![".test"]);
-        crate::set_nomangle(vec!["example.nomangle.js"]);
+        let mut registry = Vec::new();
         crate::handle_directory(
+            &test_assets(),
             PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets")),
             &tmpdir,
+            std::path::Path::new(""),
+            &mut registry,
         );
     }
 
@@ -253,6 +631,23 @@ mod tests {
         test_minify(minified, "html");
     }
 
+    #[test]
+    fn json() {
+        let minified = include_str!("../assets/example.min.json");
+        test_minify(minified, "json");
+    }
+
+    #[test]
+    fn json_preserves_order_and_numbers() {
+        // Whitespace is stripped, but key order, large integers and whitespace
+        // inside strings must survive unchanged.
+        let src = "{\n  \"b\": 10000000000000000001,\n  \"a\": \"x y\"\n}";
+        assert_eq!(
+            crate::minify_json("test.json", src),
+            "{\"b\":10000000000000000001,\"a\":\"x y\"}"
+        );
+    }
+
     #[test]
     fn dirtree() {
         let tmpdir = tempdir().expect("Failed to create test path");
@@ -275,4 +670,42 @@ mod tests {
         }
         assert!(!fs::exists(tmpdir.path().join("assets/test/file.notincluded")).unwrap());
     }
+
+    #[test]
+    fn registry() {
+        let tmpdir = tempdir().expect("Failed to create test path");
+        let mut registry = Vec::new();
+        crate::handle_directory(
+            &test_assets(),
+            PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/assets")),
+            tmpdir.path(),
+            std::path::Path::new(""),
+            &mut registry,
+        );
+        crate::write_registry(tmpdir.path(), registry);
+        let generated = fs::read_to_string(tmpdir.path().join("tcloud_assets.rs"))
+            .expect("Failed to read generated registry");
+        // Logical paths are registered relative to OUT_DIR, with content types.
+        assert!(generated.contains("\"assets/example.css\""));
+        assert!(generated.contains("\"text/css\""));
+        assert!(generated.contains("\"assets/test/file.test\""));
+        assert!(generated.contains("pub fn get(path: &str)"));
+        // Ignored files must not leak into the registry.
+        assert!(!generated.contains("file.notincluded"));
+        // With hashing off the manifest maps each path to itself.
+        assert!(generated.contains("(\"assets/example.css\", \"assets/example.css\")"));
+        assert!(generated.contains("pub fn resolve(path: &str)"));
+    }
+
+    #[test]
+    fn hashed_name() {
+        // The hash is inserted before the last extension so content-type stays detectable.
+        let name = crate::hashed_name("example.css", b"body{}");
+        assert!(name.starts_with("example."));
+        assert!(name.ends_with(".css"));
+        assert_eq!(name.matches('.').count(), 2);
+        // Hashing is deterministic and content-sensitive.
+        assert_eq!(name, crate::hashed_name("example.css", b"body{}"));
+        assert_ne!(name, crate::hashed_name("example.css", b"body{ }"));
+    }
 }